@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use lavalink_rs::{
+    gateway::LavalinkEventHandler,
+    model::{Track, TrackFinish},
+    LavalinkClient,
+};
+use serenity::{
+    async_trait,
+    client::Context,
+    model::{
+        event::VoiceServerUpdateEvent,
+        id::{ChannelId, GuildId},
+        voice::VoiceState,
+    },
+};
+
+use crate::{config::LavalinkConfig, error::PlayError};
+
+use super::{AudioBackend, TrackController, TrackEndCallback};
+
+/// Plays audio by delegating decoding/streaming to an external Lavalink
+/// node over its WebSocket protocol, instead of doing it in-process. This
+/// scales better across many guilds, at the cost of running a separate
+/// audio node. We still use songbird to perform the voice gateway
+/// handshake (`join_gateway`); the resulting connection info is handed to
+/// Lavalink instead of an in-process audio driver.
+pub struct LavalinkBackend {
+    client: LavalinkClient,
+    end_callbacks: Arc<DashMap<u64, TrackEndCallback>>,
+}
+
+impl LavalinkBackend {
+    pub async fn connect(config: &LavalinkConfig, bot_id: u64) -> Result<Self, PlayError> {
+        let end_callbacks = Arc::new(DashMap::new());
+        let client = LavalinkClient::builder(bot_id)
+            .set_host(config.host.clone())
+            .set_port(config.port)
+            .set_password(config.password.clone())
+            .build(LavalinkNodeHandler {
+                end_callbacks: end_callbacks.clone(),
+            })
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))?;
+
+        Ok(Self {
+            client,
+            end_callbacks,
+        })
+    }
+}
+
+#[async_trait]
+impl AudioBackend for LavalinkBackend {
+    async fn play(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        url: &str,
+        on_end: TrackEndCallback,
+    ) -> Result<Box<dyn TrackController>, PlayError> {
+        let manager = songbird::get(ctx).await.unwrap();
+        let (_, result) = manager.join_gateway(guild_id, channel_id).await;
+        let connection_info = result.map_err(|_| PlayError::Join)?;
+
+        self.client
+            .create_session(&connection_info)
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))?;
+
+        let query = self
+            .client
+            .auto_search_tracks(url)
+            .await
+            .map_err(|_| PlayError::Ytdl(url.to_string()))?;
+        let track: Track = query
+            .tracks
+            .into_iter()
+            .next()
+            .ok_or_else(|| PlayError::Ytdl(url.to_string()))?;
+
+        self.client
+            .play(guild_id.0, track)
+            .queue()
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))?;
+
+        // `LavalinkNodeHandler::track_finish` looks this up by guild ID when
+        // the node reports completion; there's no `set_end_callback` on the
+        // client itself, just this shared map.
+        self.end_callbacks.insert(guild_id.0, on_end);
+
+        Ok(Box::new(LavalinkTrackController {
+            client: self.client.clone(),
+            guild_id,
+        }))
+    }
+
+    async fn leave(&self, ctx: &Context, guild_id: GuildId) -> Result<(), PlayError> {
+        let manager = songbird::get(ctx).await.unwrap();
+        let _ = manager.remove(guild_id).await;
+        let _ = self.client.destroy(guild_id.0).await;
+        self.end_callbacks.remove(&guild_id.0);
+        Ok(())
+    }
+
+    async fn voice_state_update(&self, update: VoiceState) {
+        self.client.process_voice_state_update(update).await;
+    }
+
+    async fn voice_server_update(&self, update: VoiceServerUpdateEvent) {
+        self.client.process_voice_server_update(update).await;
+    }
+}
+
+/// Handles node-level gateway events. Track completion is reported here too
+/// (`track_finish`), so this is where we look up the `TrackEndCallback`
+/// `LavalinkBackend::play` stashed for the finishing guild and invoke it,
+/// which is what lets `InternalHandler::check_guild_queue` advance the queue
+/// the same way it does for the local backend.
+struct LavalinkNodeHandler {
+    end_callbacks: Arc<DashMap<u64, TrackEndCallback>>,
+}
+
+#[async_trait]
+impl LavalinkEventHandler for LavalinkNodeHandler {
+    async fn track_finish(&self, _client: LavalinkClient, event: TrackFinish) {
+        log::info!("lavalink track finished in guild {}", event.guild_id);
+
+        if let Some(on_end) = self.end_callbacks.get(&event.guild_id) {
+            on_end();
+        }
+    }
+}
+
+struct LavalinkTrackController {
+    client: LavalinkClient,
+    guild_id: GuildId,
+}
+
+#[async_trait]
+impl TrackController for LavalinkTrackController {
+    async fn stop(&self) -> Result<(), PlayError> {
+        self.client
+            .stop(self.guild_id.0)
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn pause(&self) -> Result<(), PlayError> {
+        self.client
+            .pause(self.guild_id.0)
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn resume(&self) -> Result<(), PlayError> {
+        self.client
+            .resume(self.guild_id.0)
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn set_volume(&self, volume: f32) -> Result<(), PlayError> {
+        let lavalink_volume = (volume * 100.0).round() as u16;
+        self.client
+            .volume(self.guild_id.0, lavalink_volume)
+            .await
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+}
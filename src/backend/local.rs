@@ -0,0 +1,90 @@
+use serenity::{
+    async_trait,
+    client::Context,
+    model::id::{ChannelId, GuildId},
+};
+use songbird::{tracks::TrackHandle, Event, TrackEvent};
+
+use crate::error::PlayError;
+
+use super::{AudioBackend, TrackController, TrackEndCallback};
+
+/// Plays audio by decoding it in-process with ffmpeg and streaming it into
+/// the guild's voice channel through songbird. This is the original
+/// playback path: simple to run, but every guild's decoding happens inside
+/// the bot process.
+#[derive(Default)]
+pub struct LocalBackend;
+
+#[async_trait]
+impl AudioBackend for LocalBackend {
+    async fn play(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        url: &str,
+        on_end: TrackEndCallback,
+    ) -> Result<Box<dyn TrackController>, PlayError> {
+        let songbird = songbird::get(ctx).await.unwrap();
+        let (call, result) = songbird.join(guild_id, channel_id).await;
+        result.map_err(|_| PlayError::Join)?;
+
+        let source = songbird::ffmpeg(url).await.map_err(|_| PlayError::Ffmpeg)?;
+        let handle = call.lock().await.play_source(source);
+        handle
+            .add_event(Event::Track(TrackEvent::End), LocalTrackEndHandler { on_end })
+            .map_err(|e| PlayError::Unknown(Box::new(e)))?;
+
+        Ok(Box::new(LocalTrackController { handle }))
+    }
+
+    async fn leave(&self, ctx: &Context, guild_id: GuildId) -> Result<(), PlayError> {
+        let songbird = songbird::get(ctx).await.unwrap();
+        let _ = songbird.remove(guild_id).await;
+        Ok(())
+    }
+}
+
+struct LocalTrackEndHandler {
+    on_end: TrackEndCallback,
+}
+
+#[async_trait]
+impl songbird::EventHandler for LocalTrackEndHandler {
+    async fn act(&self, _: &songbird::EventContext<'_>) -> Option<Event> {
+        (self.on_end)();
+        None
+    }
+}
+
+struct LocalTrackController {
+    handle: TrackHandle,
+}
+
+#[async_trait]
+impl TrackController for LocalTrackController {
+    async fn stop(&self) -> Result<(), PlayError> {
+        self.handle
+            .stop()
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn pause(&self) -> Result<(), PlayError> {
+        self.handle
+            .pause()
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn resume(&self) -> Result<(), PlayError> {
+        self.handle
+            .play()
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+
+    async fn set_volume(&self, volume: f32) -> Result<(), PlayError> {
+        self.handle
+            .set_volume(volume)
+            .map_err(|e| PlayError::Unknown(Box::new(e)))
+    }
+}
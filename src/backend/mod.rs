@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use serenity::{
+    async_trait,
+    client::Context,
+    model::{
+        event::VoiceServerUpdateEvent,
+        id::{ChannelId, GuildId},
+        voice::VoiceState,
+    },
+};
+
+use crate::error::PlayError;
+
+pub mod lavalink;
+pub mod local;
+
+pub use lavalink::LavalinkBackend;
+pub use local::LocalBackend;
+
+/// Invoked (off of whatever task the backend uses internally) when a track
+/// finishes, so `InternalHandler::check_guild_queue` can advance the queue
+/// the same way regardless of which backend played the track.
+pub type TrackEndCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// Drives playback for a single guild. Implemented once for the in-process
+/// ffmpeg/songbird pipeline and once for an external Lavalink node, so the
+/// `Guild` queue state machine in `bot.rs` doesn't need to know which one is
+/// in use.
+#[async_trait]
+pub trait AudioBackend: Send + Sync {
+    /// Joins `channel_id` and starts streaming `url`, calling `on_end` once
+    /// the track finishes.
+    async fn play(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        url: &str,
+        on_end: TrackEndCallback,
+    ) -> Result<Box<dyn TrackController>, PlayError>;
+
+    /// Leaves the guild's voice channel, if connected.
+    async fn leave(&self, ctx: &Context, guild_id: GuildId) -> Result<(), PlayError>;
+
+    /// Forwards a voice state update from serenity's gateway. Only the
+    /// Lavalink backend needs these, to keep its external node's voice
+    /// connection in sync; the default implementation does nothing.
+    async fn voice_state_update(&self, _update: VoiceState) {}
+
+    /// Forwards a voice server update from serenity's gateway. See
+    /// `voice_state_update`.
+    async fn voice_server_update(&self, _update: VoiceServerUpdateEvent) {}
+}
+
+/// A handle to a single in-flight track, returned by `AudioBackend::play`.
+/// `async` so the Lavalink implementation can actually report a rejected or
+/// failed node call back to the caller instead of firing it off and hoping.
+#[async_trait]
+pub trait TrackController: Send + Sync {
+    /// Stops the track immediately.
+    async fn stop(&self) -> Result<(), PlayError>;
+
+    /// Pauses the track in place.
+    async fn pause(&self) -> Result<(), PlayError>;
+
+    /// Resumes a paused track.
+    async fn resume(&self) -> Result<(), PlayError>;
+
+    /// Sets the playback volume, where `1.0` is the original volume.
+    async fn set_volume(&self, volume: f32) -> Result<(), PlayError>;
+}
@@ -1,49 +1,80 @@
 use std::{collections::VecDeque, sync::Arc};
 
 use dashmap::{mapref::one::RefMut, DashMap};
+use rand::Rng;
 use serenity::{
     async_trait,
+    builder::{CreateComponents, CreateEmbed},
     client::{Context, EventHandler},
     model::{
+        event::VoiceServerUpdateEvent,
         id::{ChannelId, GuildId, UserId},
         interactions::{
             application_command::{
                 ApplicationCommand, ApplicationCommandInteraction, ApplicationCommandOptionType,
             },
+            message_component::{ButtonStyle, MessageComponentInteraction},
             Interaction, InteractionResponseType,
         },
+        voice::VoiceState,
     },
 };
-use songbird::{tracks::TrackHandle, Event, TrackEvent};
 use youtube_dl::{YoutubeDl, YoutubeDlOutput};
 
-use crate::{config::Config, error::PlayError};
+use crate::{
+    backend::{AudioBackend, TrackController, TrackEndCallback},
+    config::Config,
+    error::PlayError,
+    playlist::{PlaylistSong, PlaylistStore},
+};
+
+/// Number of queue entries shown per page of the `queue` command.
+const QUEUE_PAGE_SIZE: usize = 10;
 
 pub struct Handler {
     internal: Arc<InternalHandler>,
     config: Arc<Config>,
+    playlists: Arc<PlaylistStore>,
 }
 
 impl Handler {
-    pub fn new(config: Arc<Config>) -> Self {
+    pub fn new(
+        config: Arc<Config>,
+        playlists: Arc<PlaylistStore>,
+        backend: Arc<dyn AudioBackend>,
+    ) -> Self {
         Self {
-            internal: Arc::default(),
+            internal: Arc::new(InternalHandler::new(backend)),
             config,
+            playlists,
         }
     }
 }
 
-#[derive(Default)]
 pub struct InternalHandler {
     guilds: DashMap<GuildId, Guild>,
+    backend: Arc<dyn AudioBackend>,
+}
+
+impl InternalHandler {
+    fn new(backend: Arc<dyn AudioBackend>) -> Self {
+        Self {
+            guilds: DashMap::new(),
+            backend,
+        }
+    }
 }
 
-#[derive(Debug)]
 pub struct Guild {
     channel_id: ChannelId,
-    handle: Option<TrackHandle>,
+    handle: Option<Arc<dyn TrackController>>,
     now_playing: Option<Song>,
     queue: VecDeque<Song>,
+    loop_mode: LoopMode,
+    /// Playback volume, where `1.0` is the original volume. Re-applied to
+    /// each new track `check_guild_queue` starts so it survives across
+    /// songs instead of resetting to full volume.
+    volume: f32,
 }
 
 impl Guild {
@@ -53,6 +84,45 @@ impl Guild {
             handle: None,
             now_playing: None,
             queue: VecDeque::new(),
+            loop_mode: LoopMode::default(),
+            volume: 1.0,
+        }
+    }
+}
+
+/// Controls what happens to `now_playing` when a track finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Play through the queue once, as normal.
+    Off,
+    /// Replay the current song indefinitely.
+    Track,
+    /// Cycle through the queue indefinitely, requeueing each song as it finishes.
+    Queue,
+}
+
+impl Default for LoopMode {
+    fn default() -> Self {
+        LoopMode::Off
+    }
+}
+
+impl LoopMode {
+    fn from_option(value: &str) -> Self {
+        match value {
+            "track" => LoopMode::Track,
+            "queue" => LoopMode::Queue,
+            _ => LoopMode::Off,
+        }
+    }
+}
+
+impl std::fmt::Display for LoopMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoopMode::Off => write!(f, "off"),
+            LoopMode::Track => write!(f, "track"),
+            LoopMode::Queue => write!(f, "queue"),
         }
     }
 }
@@ -60,6 +130,82 @@ impl Guild {
 #[derive(Debug)]
 pub struct Song {
     url: String,
+    title: Option<String>,
+    duration: Option<u64>,
+}
+
+impl Song {
+    /// Renders this song as a single queue line, e.g. `Some Title (3:45)`,
+    /// falling back to the raw URL when ytdl didn't give us metadata.
+    fn describe(&self) -> String {
+        match (&self.title, self.duration) {
+            (Some(title), Some(duration)) => format!("{} ({})", title, format_duration(duration)),
+            (Some(title), None) => title.clone(),
+            (None, _) => self.url.clone(),
+        }
+    }
+}
+
+/// Formats a duration given in seconds as `m:ss`.
+fn format_duration(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Runs `url` (or a search query) through ytdl and converts the result into
+/// the `Song`s it expands to, shared by `play` and `playnext` so they only
+/// differ in where the songs end up in the queue.
+async fn resolve_songs(url: &str) -> Result<(Vec<Song>, YoutubeDlOutput), PlayError> {
+    let task_query = format!("ytsearch1:{}", url);
+    let yt_resp = tokio::spawn(async move {
+        YoutubeDl::new(task_query)
+            .socket_timeout("15")
+            .format("bestaudio")
+            .run()
+    })
+    .await
+    .map_err(|e| PlayError::Unknown(Box::new(e)))?
+    .map_err(|_| PlayError::Ytdl(url.to_string()))?;
+
+    let songs = match &yt_resp {
+        YoutubeDlOutput::Playlist(playlist) => {
+            let entries = playlist
+                .entries
+                .as_ref()
+                .ok_or_else(|| PlayError::Ytdl(url.to_string()))?;
+
+            entries
+                .iter()
+                .map(|entry| {
+                    Ok(Song {
+                        url: entry
+                            .url
+                            .clone()
+                            .ok_or_else(|| PlayError::Ytdl(url.to_string()))?,
+                        title: Some(entry.title.clone()),
+                        duration: entry
+                            .duration
+                            .as_ref()
+                            .and_then(|d| d.as_f64())
+                            .map(|d| d as u64),
+                    })
+                })
+                .collect::<Result<Vec<_>, PlayError>>()?
+        }
+        YoutubeDlOutput::SingleVideo(vid) => vec![Song {
+            url: vid
+                .url
+                .clone()
+                .ok_or_else(|| PlayError::Ytdl(url.to_string()))?,
+            title: Some(vid.title.clone()),
+            duration: vid
+                .duration
+                .as_ref()
+                .and_then(|d| d.as_f64())
+                .map(|d| d as u64),
+        }],
+    };
+
+    Ok((songs, yt_resp))
 }
 
 #[async_trait]
@@ -83,6 +229,77 @@ impl EventHandler for Handler {
                     cmd.name("skip")
                         .description("Skips the currently playing song.")
                 })
+                .create_application_command(|cmd| {
+                    cmd.name("queue")
+                        .description("Shows the current song queue.")
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("loop")
+                        .description("Sets the loop mode for the queue.")
+                        .create_option(|opt| {
+                            opt.name("mode")
+                                .description("Loop mode to use.")
+                                .kind(ApplicationCommandOptionType::String)
+                                .add_string_choice("Off", "off")
+                                .add_string_choice("Track", "track")
+                                .add_string_choice("Queue", "queue")
+                                .required(true)
+                        })
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("saveplaylist")
+                        .description("Saves the current queue as a named playlist.")
+                        .create_option(|opt| {
+                            opt.name("name")
+                                .description("Name to save the playlist as.")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("playlists")
+                        .description("Lists your saved playlists.")
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("loadplaylist")
+                        .description("Loads a saved playlist into the queue.")
+                        .create_option(|opt| {
+                            opt.name("name")
+                                .description("Name of the playlist to load.")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("pause").description("Pauses the current song.")
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("resume").description("Resumes the current song.")
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("volume")
+                        .description("Sets the playback volume.")
+                        .create_option(|opt| {
+                            opt.name("level")
+                                .description("Volume percentage, from 0 to 200.")
+                                .kind(ApplicationCommandOptionType::Integer)
+                                .required(true)
+                        })
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("shuffle")
+                        .description("Shuffles the upcoming songs in the queue.")
+                })
+                .create_application_command(|cmd| {
+                    cmd.name("playnext")
+                        .description("Adds a track to the front of the queue, playing next.")
+                        .create_option(|opt| {
+                            opt.name("song")
+                                .description("URL to the song to play next.")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
             })
             .await
             .expect("Failed to create bot commands");
@@ -98,41 +315,70 @@ impl EventHandler for Handler {
     ) {
         log::info!("interaction received");
 
-        if let Interaction::ApplicationCommand(cmd) = interaction {
-            let _ = cmd
-                .create_interaction_response(&ctx.http, |resp| {
-                    resp.kind(InteractionResponseType::DeferredChannelMessageWithSource)
-                })
-                .await;
-
-            let response = match cmd.data.name.as_str() {
-                "play" => self.handle_play(ctx.clone(), &cmd).await,
-                "skip" => self.handle_skip(ctx.clone(), &cmd).await,
-                _ => return,
-            };
+        match interaction {
+            Interaction::ApplicationCommand(cmd) => {
+                let _ = cmd
+                    .create_interaction_response(&ctx.http, |resp| {
+                        resp.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    })
+                    .await;
 
-            if let Err(e) = response {
-                match e {
-                    PlayError::Unknown(e) => {
-                        log::warn!("internal command error: {:?}", e);
-                        let _ = cmd
-                            .edit_original_interaction_response(&ctx.http, |resp| {
-                                resp.content("Something went wrong. Give it another go?")
-                            })
-                            .await;
-                    }
-                    e => {
-                        log::warn!("user command error: {:?}", &e);
-                        let _ = cmd
-                            .edit_original_interaction_response(&ctx.http, |resp| {
-                                resp.content(e.to_string())
-                            })
-                            .await;
-                    }
+                let response = match cmd.data.name.as_str() {
+                    "play" => self.handle_play(ctx.clone(), &cmd).await,
+                    "skip" => self.handle_skip(ctx.clone(), &cmd).await,
+                    "queue" => self.handle_queue(ctx.clone(), &cmd).await,
+                    "loop" => self.handle_loop(ctx.clone(), &cmd).await,
+                    "saveplaylist" => self.handle_save_playlist(ctx.clone(), &cmd).await,
+                    "playlists" => self.handle_list_playlists(ctx.clone(), &cmd).await,
+                    "loadplaylist" => self.handle_load_playlist(ctx.clone(), &cmd).await,
+                    "pause" => self.handle_pause(ctx.clone(), &cmd).await,
+                    "resume" => self.handle_resume(ctx.clone(), &cmd).await,
+                    "volume" => self.handle_volume(ctx.clone(), &cmd).await,
+                    "shuffle" => self.handle_shuffle(ctx.clone(), &cmd).await,
+                    "playnext" => self.handle_playnext(ctx.clone(), &cmd).await,
+                    _ => return,
                 };
+
+                if let Err(e) = response {
+                    match e {
+                        PlayError::Unknown(e) => {
+                            log::warn!("internal command error: {:?}", e);
+                            let _ = cmd
+                                .edit_original_interaction_response(&ctx.http, |resp| {
+                                    resp.content("Something went wrong. Give it another go?")
+                                })
+                                .await;
+                        }
+                        e => {
+                            log::warn!("user command error: {:?}", &e);
+                            let _ = cmd
+                                .edit_original_interaction_response(&ctx.http, |resp| {
+                                    resp.content(e.to_string())
+                                })
+                                .await;
+                        }
+                    };
+                }
             }
+            Interaction::MessageComponent(cmd) if cmd.data.custom_id.starts_with("queue:") => {
+                self.handle_queue_page(ctx, cmd).await;
+            }
+            _ => {}
         }
     }
+
+    async fn voice_state_update(
+        &self,
+        _ctx: Context,
+        _old: Option<VoiceState>,
+        new: VoiceState,
+    ) {
+        self.internal.backend.voice_state_update(new).await;
+    }
+
+    async fn voice_server_update(&self, _ctx: Context, update: VoiceServerUpdateEvent) {
+        self.internal.backend.voice_server_update(update).await;
+    }
 }
 
 impl Handler {
@@ -151,7 +397,6 @@ impl Handler {
             .and_then(|opt| opt.value.as_ref())
             .and_then(|val| val.as_str())
             .ok_or(PlayError::NoUrl)?;
-        let task_query = format!("ytsearch1:{}", url);
 
         let channel_id = self
             .get_user_channel(&ctx, guild_id, cmd.user.id)
@@ -164,49 +409,70 @@ impl Handler {
             guild_id,
             channel_id
         );
-        let yt_resp = tokio::spawn(async move {
-            YoutubeDl::new(task_query)
-                .socket_timeout("15")
-                .format("bestaudio")
-                .run()
-        })
-        .await
-        .map_err(|e| PlayError::Unknown(Box::new(e)))?
-        .map_err(|_| PlayError::Ytdl(url.to_string()))?;
+        let (songs, yt_resp) = resolve_songs(url).await?;
         log::info!("ytdl success");
 
         {
             let mut guild = self.get_guild(guild_id, channel_id);
+            let start = guild.queue.len();
+            guild.queue.extend(songs);
 
             match yt_resp {
                 YoutubeDlOutput::Playlist(playlist) => {
-                    let entries = playlist
-                        .entries
-                        .as_ref()
-                        .ok_or_else(|| PlayError::Ytdl(url.to_string()))?;
-
-                    let start = guild.queue.len();
-                    for entry in entries {
-                        guild.queue.push_back(Song {
-                            url: entry
-                                .url
-                                .clone()
-                                .ok_or_else(|| PlayError::Ytdl(url.to_string()))?,
-                        });
-                    }
-
                     playlist.build_response(&ctx, cmd, start + 1).await?;
                 }
                 YoutubeDlOutput::SingleVideo(vid) => {
-                    guild.queue.push_back(Song {
-                        url: vid
-                            .url
-                            .clone()
-                            .ok_or_else(|| PlayError::Ytdl(url.to_string()))?,
-                    });
-                    let pos = guild.queue.len();
+                    vid.build_response(&ctx, cmd, guild.queue.len()).await?;
+                }
+            }
+        }
+
+        self.internal.check_guild_queue(guild_id, &ctx).await?;
+        Ok(())
+    }
+
+    pub async fn handle_playnext(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received playnext command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let url = cmd
+            .data
+            .options
+            .first()
+            .and_then(|opt| opt.value.as_ref())
+            .and_then(|val| val.as_str())
+            .ok_or(PlayError::NoUrl)?;
+
+        let channel_id = self
+            .get_user_channel(&ctx, guild_id, cmd.user.id)
+            .await
+            .ok_or(PlayError::NoChannel)?;
+
+        log::info!(
+            "running ytdl for playnext url `{}`, guild {} channel {}",
+            url,
+            guild_id,
+            channel_id
+        );
+        let (songs, yt_resp) = resolve_songs(url).await?;
+        log::info!("ytdl success");
+
+        {
+            let mut guild = self.get_guild(guild_id, channel_id);
+            for song in songs.into_iter().rev() {
+                guild.queue.push_front(song);
+            }
 
-                    vid.build_response(&ctx, cmd, pos).await?;
+            match yt_resp {
+                YoutubeDlOutput::Playlist(playlist) => {
+                    playlist.build_response(&ctx, cmd, 1).await?;
+                }
+                YoutubeDlOutput::SingleVideo(vid) => {
+                    vid.build_response(&ctx, cmd, 1).await?;
                 }
             }
         }
@@ -215,22 +481,60 @@ impl Handler {
         Ok(())
     }
 
-    pub async fn handle_skip(
+    pub async fn handle_shuffle(
         &self,
         ctx: Context,
         cmd: &ApplicationCommandInteraction,
     ) -> Result<(), PlayError> {
-        log::info!("received skip command");
+        log::info!("received shuffle command");
 
         let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
-        let guild = self
+        let mut guild = self
             .internal
             .guilds
             .get_mut(&guild_id)
             .ok_or(PlayError::BotNotPlaying)?;
 
-        if let Some(handle) = guild.handle.as_ref() {
-            handle.stop().map_err(|e| PlayError::Unknown(Box::new(e)))?;
+        let mut rng = rand::thread_rng();
+        for i in (1..guild.queue.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            guild.queue.swap(i, j);
+        }
+        drop(guild);
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.content("🔀 Shuffled the queue.")
+            })
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_skip(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received skip command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let handle = {
+            let mut guild = self
+                .internal
+                .guilds
+                .get_mut(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            // In `Track` loop mode `on_track_end` replays `now_playing`, so
+            // drop it here to move on to the next song instead.
+            if guild.loop_mode == LoopMode::Track {
+                guild.now_playing = None;
+            }
+            guild.handle.clone()
+        };
+
+        if let Some(handle) = handle {
+            handle.stop().await?;
         }
 
         let _ = cmd
@@ -240,6 +544,317 @@ impl Handler {
         Ok(())
     }
 
+    pub async fn handle_queue(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received queue command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let (embed, components) = {
+            let guild = self
+                .internal
+                .guilds
+                .get(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            build_queue_page(&guild, 0)
+        };
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.set_embed(embed).components(|c| {
+                    *c = components;
+                    c
+                })
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Handles the ◀/▶ button presses on a `queue` embed, re-rendering it in
+    /// place for the requested page.
+    async fn handle_queue_page(&self, ctx: Context, cmd: MessageComponentInteraction) {
+        log::info!("received queue page interaction");
+
+        let guild_id = match cmd.guild_id {
+            Some(id) => id,
+            None => return,
+        };
+
+        let mut parts = cmd.data.custom_id.split(':').skip(1);
+        let direction = parts.next();
+        let page: usize = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+        let page = match direction {
+            Some("next") => page + 1,
+            Some("prev") => page.saturating_sub(1),
+            _ => page,
+        };
+
+        let guild = match self.internal.guilds.get(&guild_id) {
+            Some(guild) => guild,
+            None => return,
+        };
+        let (embed, components) = build_queue_page(&guild, page);
+        drop(guild);
+
+        let _ = cmd
+            .create_interaction_response(&ctx.http, |resp| {
+                resp.kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|data| {
+                        data.set_embed(embed).components(|c| {
+                            *c = components;
+                            c
+                        })
+                    })
+            })
+            .await;
+    }
+
+    pub async fn handle_loop(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received loop command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let mode = cmd
+            .data
+            .options
+            .first()
+            .and_then(|opt| opt.value.as_ref())
+            .and_then(|val| val.as_str())
+            .ok_or(PlayError::NoLoopMode)?;
+        let loop_mode = LoopMode::from_option(mode);
+
+        {
+            let mut guild = self
+                .internal
+                .guilds
+                .get_mut(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            guild.loop_mode = loop_mode;
+        }
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.content(format!("🔁 Loop mode set to `{}`.", loop_mode))
+            })
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_save_playlist(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received saveplaylist command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let name = cmd
+            .data
+            .options
+            .first()
+            .and_then(|opt| opt.value.as_ref())
+            .and_then(|val| val.as_str())
+            .ok_or(PlayError::NoPlaylistName)?;
+
+        let songs: Vec<PlaylistSong> = {
+            let guild = self
+                .internal
+                .guilds
+                .get(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+
+            guild
+                .now_playing
+                .iter()
+                .chain(guild.queue.iter())
+                .map(|song| PlaylistSong {
+                    url: song.url.clone(),
+                    title: song.title.clone(),
+                })
+                .collect()
+        };
+
+        self.playlists.save(guild_id, cmd.user.id, name, songs)?;
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.content(format!("✅ Saved playlist `{}`.", name))
+            })
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_list_playlists(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received playlists command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let names = self.playlists.list(guild_id, cmd.user.id);
+
+        let content = if names.is_empty() {
+            "You don't have any saved playlists.".to_string()
+        } else {
+            names
+                .iter()
+                .map(|name| format!("- {}", name))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| resp.content(content))
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_load_playlist(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received loadplaylist command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let name = cmd
+            .data
+            .options
+            .first()
+            .and_then(|opt| opt.value.as_ref())
+            .and_then(|val| val.as_str())
+            .ok_or(PlayError::NoPlaylistName)?;
+
+        let songs = self.playlists.load(guild_id, cmd.user.id, name)?;
+        let added = songs.len();
+
+        let channel_id = self
+            .get_user_channel(&ctx, guild_id, cmd.user.id)
+            .await
+            .ok_or(PlayError::NoChannel)?;
+
+        {
+            let mut guild = self.get_guild(guild_id, channel_id);
+            for song in songs {
+                guild.queue.push_back(Song {
+                    url: song.url,
+                    title: song.title,
+                    duration: None,
+                });
+            }
+        }
+
+        self.internal.check_guild_queue(guild_id, &ctx).await?;
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.content(format!("✅ Loaded {} song(s) from `{}`.", added, name))
+            })
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_pause(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received pause command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let handle = {
+            let guild = self
+                .internal
+                .guilds
+                .get(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            guild.handle.clone().ok_or(PlayError::BotNotPlaying)?
+        };
+        handle.pause().await?;
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| resp.content("⏸️ Paused."))
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_resume(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received resume command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let handle = {
+            let guild = self
+                .internal
+                .guilds
+                .get(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            guild.handle.clone().ok_or(PlayError::BotNotPlaying)?
+        };
+        handle.resume().await?;
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| resp.content("▶️ Resumed."))
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn handle_volume(
+        &self,
+        ctx: Context,
+        cmd: &ApplicationCommandInteraction,
+    ) -> Result<(), PlayError> {
+        log::info!("received volume command");
+
+        let guild_id = cmd.guild_id.ok_or(PlayError::NoGuildId)?;
+        let level = cmd
+            .data
+            .options
+            .first()
+            .and_then(|opt| opt.value.as_ref())
+            .and_then(|val| val.as_i64())
+            .ok_or(PlayError::NoVolume)?
+            .clamp(0, 200);
+        let volume = level as f32 / 100.0;
+
+        let handle = {
+            let mut guild = self
+                .internal
+                .guilds
+                .get_mut(&guild_id)
+                .ok_or(PlayError::BotNotPlaying)?;
+            guild.volume = volume;
+            guild.handle.clone()
+        };
+        if let Some(handle) = handle {
+            handle.set_volume(volume).await?;
+        }
+
+        let _ = cmd
+            .edit_original_interaction_response(&ctx.http, |resp| {
+                resp.content(format!("🔊 Volume set to {}%.", level))
+            })
+            .await;
+
+        Ok(())
+    }
+
     async fn get_user_channel(
         &self,
         ctx: &Context,
@@ -283,72 +898,161 @@ impl InternalHandler {
     ) -> Result<(), PlayError> {
         log::info!("guild {} checking queue", guild_id);
 
-        let mut guild = self.guilds.get_mut(&guild_id).ok_or(PlayError::NoChannel)?;
-        let songbird = songbird::get(ctx).await.unwrap();
-
-        if guild.now_playing.is_none() {
-            if let Some(new) = guild.queue.pop_front() {
-                log::info!("guild {} playing {:?}", guild_id, &new);
-
-                let (call, result) = songbird.join(guild_id, guild.channel_id).await;
-                result.map_err(|_| PlayError::Join)?;
-
-                let source = songbird::ffmpeg(&new.url)
-                    .await
-                    .map_err(|_| PlayError::Ffmpeg)?;
-                let handle = call.lock().await.play_source(source);
-                handle
-                    .add_event(
-                        Event::Track(TrackEvent::End),
-                        SongEndHandler {
-                            ctx: ctx.clone(),
-                            guild_id,
-                            handler: self.clone(),
-                        },
-                    )
-                    .map_err(|e| PlayError::Unknown(Box::new(e)))?;
-
-                guild.handle.replace(handle);
-                guild.now_playing.replace(new);
+        enum NextUp {
+            Play {
+                channel_id: ChannelId,
+                volume: f32,
+                song: Song,
+            },
+            QueueEmpty,
+            AlreadyPlaying,
+        }
+
+        // Only ever hold the `DashMap` guard across plain field access, never
+        // across an `.await` on the backend: a shard's lock isn't scoped to
+        // one guild, so a slow `play`/`leave` round-trip (especially against
+        // an external Lavalink node) would stall lookups for unrelated
+        // guilds. Grab what we need, drop the guard, do the async work, then
+        // re-acquire to store the result.
+        let next = {
+            let mut guild = self.guilds.get_mut(&guild_id).ok_or(PlayError::NoChannel)?;
+
+            if guild.handle.is_some() {
+                NextUp::AlreadyPlaying
             } else {
+                // In `Track` loop mode `on_track_end` leaves `now_playing` set
+                // so we replay it here instead of pulling the next song off
+                // the queue.
+                match guild.now_playing.take().or_else(|| guild.queue.pop_front()) {
+                    Some(song) => NextUp::Play {
+                        channel_id: guild.channel_id,
+                        volume: guild.volume,
+                        song,
+                    },
+                    None => NextUp::QueueEmpty,
+                }
+            }
+        };
+
+        match next {
+            NextUp::Play {
+                channel_id,
+                volume,
+                song,
+            } => {
+                log::info!("guild {} playing {:?}", guild_id, &song);
+
+                let handler = self.clone();
+                let end_ctx = ctx.clone();
+                let on_end: TrackEndCallback = Arc::new(move || {
+                    let handler = handler.clone();
+                    let ctx = end_ctx.clone();
+                    tokio::spawn(async move { handler.on_track_end(guild_id, &ctx).await });
+                });
+
+                let controller: Arc<dyn TrackController> = Arc::from(
+                    self.backend
+                        .play(ctx, guild_id, channel_id, &song.url, on_end)
+                        .await?,
+                );
+                let _ = controller.set_volume(volume).await;
+
+                if let Some(mut guild) = self.guilds.get_mut(&guild_id) {
+                    guild.handle.replace(controller);
+                    guild.now_playing.replace(song);
+                }
+            }
+            NextUp::QueueEmpty => {
                 log::info!("guild {} queue empty, leaving channel", guild_id);
 
-                let _ = songbird.remove(guild_id).await;
-                drop(guild);
+                let _ = self.backend.leave(ctx, guild_id).await;
                 self.guilds.remove(&guild_id);
             }
-        } else {
-            log::info!("guild {} song already playing", guild_id);
+            NextUp::AlreadyPlaying => {
+                log::info!("guild {} song already playing", guild_id);
+            }
         }
 
         Ok(())
     }
-}
-
-struct SongEndHandler {
-    ctx: Context,
-    guild_id: GuildId,
-    handler: Arc<InternalHandler>,
-}
 
-#[async_trait]
-impl songbird::EventHandler for SongEndHandler {
-    async fn act(&self, _: &songbird::EventContext<'_>) -> Option<Event> {
-        log::info!("guild {} song finished", self.guild_id);
+    /// Called back by the active `AudioBackend` once a track finishes,
+    /// regardless of whether it was played locally or via Lavalink.
+    async fn on_track_end(self: &Arc<Self>, guild_id: GuildId, ctx: &Context) {
+        log::info!("guild {} song finished", guild_id);
 
         {
-            let mut guild = self.handler.guilds.get_mut(&self.guild_id)?;
-            guild.now_playing = None;
+            let mut guild = match self.guilds.get_mut(&guild_id) {
+                Some(guild) => guild,
+                None => return,
+            };
             guild.handle = None;
+
+            match guild.loop_mode {
+                LoopMode::Off => guild.now_playing = None,
+                // Left in place so `check_guild_queue` replays it.
+                LoopMode::Track => {}
+                LoopMode::Queue => {
+                    if let Some(song) = guild.now_playing.take() {
+                        guild.queue.push_back(song);
+                    }
+                }
+            }
         }
 
-        let _ = self
-            .handler
-            .check_guild_queue(self.guild_id, &self.ctx)
-            .await;
+        let _ = self.check_guild_queue(guild_id, ctx).await;
+    }
+}
+
+/// Builds the embed and ◀/▶ button row for one page of a guild's queue.
+fn build_queue_page(guild: &Guild, page: usize) -> (CreateEmbed, CreateComponents) {
+    let total_pages = guild.queue.len().saturating_sub(1) / QUEUE_PAGE_SIZE + 1;
+    let page = page.min(total_pages - 1);
+    let start = page * QUEUE_PAGE_SIZE;
 
-        None
+    let mut embed = CreateEmbed::default();
+    embed.title("Queue");
+
+    if let Some(now_playing) = &guild.now_playing {
+        embed.field("Now playing", now_playing.describe(), false);
     }
+
+    let entries: String = guild
+        .queue
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(QUEUE_PAGE_SIZE)
+        .map(|(i, song)| format!("{}. {}\n", i + 1, song.describe()))
+        .collect();
+    embed.field(
+        "Up next",
+        if entries.is_empty() {
+            "Nothing queued.".to_string()
+        } else {
+            entries
+        },
+        false,
+    );
+    embed.footer(|f| f.text(format!("Page {}/{}", page + 1, total_pages)));
+
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_button(|b| {
+            b.custom_id(format!("queue:prev:{}", page))
+                .label("◀")
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(format!("queue:next:{}", page))
+                .label("▶")
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= total_pages)
+        })
+    });
+
+    (embed, components)
 }
 
 #[async_trait]
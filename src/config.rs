@@ -7,6 +7,23 @@ use crate::error::ConfigError;
 pub struct Config {
     pub token: String,
     pub app_id: u64,
+    /// Path to the JSON file used to persist saved playlists.
+    #[serde(default = "default_playlists_path")]
+    pub playlists_path: String,
+    /// When present, play audio through an external Lavalink node instead
+    /// of decoding it in-process.
+    pub lavalink: Option<LavalinkConfig>,
+}
+
+fn default_playlists_path() -> String {
+    "playlists.json".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LavalinkConfig {
+    pub host: String,
+    pub port: u16,
+    pub password: String,
 }
 
 impl Config {
@@ -24,7 +24,29 @@ pub enum PlayError {
     Ffmpeg,
     #[error("Join a voice channel before trying to queue a song.")]
     NoChannel,
+    #[error("Nothing is playing right now.")]
+    BotNotPlaying,
+    #[error("You must provide a loop mode.")]
+    NoLoopMode,
+    #[error("You must provide a playlist name.")]
+    NoPlaylistName,
+    #[error("You must provide a volume level.")]
+    NoVolume,
+    #[error(transparent)]
+    Playlist(#[from] PlaylistError),
 
     #[error("Unknown play command error: {0:?}")]
     Unknown(DynError),
 }
+
+#[derive(Debug, Error)]
+pub enum PlaylistError {
+    #[error("Could not read or write file `{0}`: {1:?}")]
+    Io(String, std::io::Error),
+    #[error("Invalid playlist storage content in `{0}`: {1:?}")]
+    InvalidContent(String, serde_json::Error),
+    #[error("You already have a playlist named `{0}`.")]
+    AlreadyExists(String),
+    #[error("No playlist named `{0}` was found.")]
+    NotFound(String),
+}
@@ -1,21 +1,32 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use backend::{AudioBackend, LavalinkBackend, LocalBackend};
 use bot::Handler;
 use config::Config;
+use playlist::PlaylistStore;
 use serenity::Client;
 use songbird::SerenityInit;
 
+mod backend;
 mod bot;
 mod config;
 mod error;
+mod playlist;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
     let config = Arc::new(Config::from_path("config.toml")?);
-    let handler = Handler::new(config.clone());
+    let playlists = Arc::new(PlaylistStore::open(&config.playlists_path)?);
+
+    let backend: Arc<dyn AudioBackend> = match &config.lavalink {
+        Some(lavalink) => Arc::new(LavalinkBackend::connect(lavalink, config.app_id).await?),
+        None => Arc::new(LocalBackend::default()),
+    };
+
+    let handler = Handler::new(config.clone(), playlists, backend);
 
     Client::builder(config.token.clone())
         .event_handler(handler)
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+
+use crate::error::PlaylistError;
+
+/// A single song as stored in a saved playlist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistSong {
+    pub url: String,
+    pub title: Option<String>,
+}
+
+type NamedPlaylists = HashMap<String, Vec<PlaylistSong>>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PlaylistData {
+    #[serde(default)]
+    guilds: HashMap<u64, HashMap<u64, NamedPlaylists>>,
+}
+
+/// JSON-backed storage for named playlists, keyed by guild and then by the
+/// user who saved them. Reads the whole file in on startup and rewrites it
+/// on every mutation; saved playlists are a low-frequency feature so this
+/// keeps the implementation simple.
+pub struct PlaylistStore {
+    path: PathBuf,
+    data: Mutex<PlaylistData>,
+}
+
+impl PlaylistStore {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, PlaylistError> {
+        let path = path.into();
+
+        let data = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| PlaylistError::Io(path.display().to_string(), e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| PlaylistError::InvalidContent(path.display().to_string(), e))?
+        } else {
+            PlaylistData::default()
+        };
+
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    pub fn save(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        name: &str,
+        songs: Vec<PlaylistSong>,
+    ) -> Result<(), PlaylistError> {
+        let mut data = self.data.lock().unwrap();
+        let playlists = data
+            .guilds
+            .entry(guild_id.0)
+            .or_default()
+            .entry(user_id.0)
+            .or_default();
+
+        if playlists.contains_key(name) {
+            return Err(PlaylistError::AlreadyExists(name.to_string()));
+        }
+
+        playlists.insert(name.to_string(), songs);
+        self.flush(&data)
+    }
+
+    pub fn load(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        name: &str,
+    ) -> Result<Vec<PlaylistSong>, PlaylistError> {
+        let data = self.data.lock().unwrap();
+        data.guilds
+            .get(&guild_id.0)
+            .and_then(|users| users.get(&user_id.0))
+            .and_then(|playlists| playlists.get(name))
+            .cloned()
+            .ok_or_else(|| PlaylistError::NotFound(name.to_string()))
+    }
+
+    pub fn list(&self, guild_id: GuildId, user_id: UserId) -> Vec<String> {
+        let data = self.data.lock().unwrap();
+        let mut names: Vec<String> = data
+            .guilds
+            .get(&guild_id.0)
+            .and_then(|users| users.get(&user_id.0))
+            .map(|playlists| playlists.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    fn flush(&self, data: &PlaylistData) -> Result<(), PlaylistError> {
+        let content = serde_json::to_string_pretty(data)
+            .map_err(|e| PlaylistError::InvalidContent(self.path.display().to_string(), e))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| PlaylistError::Io(self.path.display().to_string(), e))
+    }
+}